@@ -0,0 +1,51 @@
+//! Renders tokenizer/parser errors as a source line with a caret underline,
+//! e.g.:
+//!
+//! ```text
+//! 1 | SELECT headnigs FROM "x.md"
+//!           ^^^^^^^^ unknown element 'headnigs'
+//! ```
+
+use crate::parser::ParseError;
+use crate::tokenizer::TokenizationError;
+
+/// Renders `message` as a caret diagnostic under `source`'s `line` (1-based),
+/// underlining `length` columns starting at `column` (1-based).
+pub fn render(source: &str, line: usize, column: usize, length: usize, message: &str) -> String {
+    let line_content = source.lines().nth(line.saturating_sub(1)).unwrap_or("");
+    let gutter = format!("{} | ", line);
+
+    let underline_offset = " ".repeat(gutter.len() + column.saturating_sub(1));
+    let carets = "^".repeat(length.max(1));
+
+    format!("{gutter}{line_content}\n{underline_offset}{carets} {message}")
+}
+
+pub fn render_tokenization_error(source: &str, error: &TokenizationError) -> String {
+    let (line, column) = match error {
+        TokenizationError::UnexpectedCharacter { line, column, .. } => (*line, *column),
+        TokenizationError::UnexpectedEscapeSequence { line, column, .. } => (*line, *column),
+        TokenizationError::UnterminatedStringLiteral { line, column } => (*line, *column),
+    };
+
+    render(source, line, column, 1, &error.to_string())
+}
+
+pub fn render_parse_error(source: &str, error: &ParseError) -> String {
+    let (line, column, length) = error.span();
+    render(source, line, column, length, &error.to_string())
+}
+
+#[cfg(test)]
+mod diagnostics_tests {
+    use super::*;
+
+    #[test]
+    fn test_render_underlines_the_offending_span() {
+        let source = "SELECT headnigs FROM \"x.md\"";
+        let rendered = render(source, 1, 8, 8, "unknown element 'headnigs'");
+
+        let expected = "1 | SELECT headnigs FROM \"x.md\"\n           ^^^^^^^^ unknown element 'headnigs'";
+        assert_eq!(rendered, expected);
+    }
+}
@@ -1,10 +1,15 @@
+use std::path::Path;
+
+use serde::Serialize;
 use thiserror::Error;
 
-use markdown::mdast::{Node, Heading, Paragraph};
+use markdown::mdast::Node;
 use markdown::to_mdast;
 use markdown::ParseOptions;
 
-use markdownql::parser::{Query, Element};
+use markdownql::files::resolve_files;
+use markdownql::index::{IndexError, MarkdownIndex};
+use markdownql::parser::{CmpOp, Condition, Element, Field, Query, Value};
 
 #[derive(Error, Debug)]
 pub enum ExecutorError {
@@ -16,136 +21,566 @@ pub enum ExecutorError {
 
     #[error("Invalid element: {0}")]
     InvalidElement(String),
+
+    #[error("Error querying index: {0}")]
+    IndexError(#[from] IndexError),
+}
+
+/// A single extracted element, tagged with the file it came from so results
+/// from a multi-file `FROM` clause can be told apart. `level` is only ever
+/// set for headings; every other element leaves it `None`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Match {
+    pub source_file: String,
+    pub level: Option<i64>,
+    pub value: String,
+}
+
+/// A fenced code block, tagged with its (optional) fence language.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CodeMatch {
+    pub source_file: String,
+    pub language: Option<String>,
+    pub code: String,
+}
+
+/// A Markdown link, with its URL and the rendered link text.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LinkMatch {
+    pub source_file: String,
+    pub url: String,
+    pub text: String,
 }
 
-#[derive(Debug)]
+/// A GFM table, as its rows of cell text.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableMatch {
+    pub source_file: String,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// A per-file failure that didn't abort the rest of a multi-file query.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileError {
+    pub source_file: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize)]
 pub struct QueryResult {
-    pub headings: Vec<String>,
-    pub paragraphs: Vec<String>,
-    pub matching_text: Vec<String>,
+    pub headings: Vec<Match>,
+    pub paragraphs: Vec<Match>,
+    pub matching_text: Vec<Match>,
+    pub code_blocks: Vec<CodeMatch>,
+    pub links: Vec<LinkMatch>,
+    pub list_items: Vec<Match>,
+    pub tables: Vec<TableMatch>,
+    pub errors: Vec<FileError>,
 }
 
 pub struct MarkdownQueryExecutor;
 
 impl MarkdownQueryExecutor {
     pub fn execute_query(query: Query) -> Result<QueryResult, ExecutorError> {
-        // Construct file path
-        let file_path = std::env::current_dir()?.join(&query.file_path);
+        let cwd = std::env::current_dir()?;
+        let files = resolve_files(&cwd, &query.file_path)?;
 
-        // Read the Markdown file
-        let markdown_content = std::fs::read_to_string(&file_path)?;
+        let mut result = QueryResult::default();
 
-        // Parse Markdown content into AST
-        let ast = to_mdast(&markdown_content, &ParseOptions::gfm()).map_err(|e| ExecutorError::MarkdownParseError(e))?;
-
-        let mut headings = Vec::new();
-        let mut paragraphs = Vec::new();
-        let mut matching_text = Vec::new();
+        for file in files {
+            let source_file = display_path(&cwd, &file);
 
-        for element in &query.elements {
-            match element {
-                Element::Headings => {
-                    headings.extend(Self::extract_headings(&ast));
+            let ast = match Self::parse_file(&file) {
+                Ok(ast) => ast,
+                Err(e) => {
+                    result.errors.push(FileError { source_file, message: e.to_string() });
+                    continue;
                 }
-                Element::Paragraphs => {
-                    paragraphs.extend(Self::extract_paragraphs(&ast));
-                }
-                Element::Text(text) => {
-                    matching_text.extend(Self::extract_matching_text(&ast, text));
+            };
+
+            for element in &query.elements {
+                Self::collect_direct(&source_file, element, &ast, &query.condition, &mut result);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Same as [`Self::execute_query`], but serves `headings`/`paragraphs`/
+    /// `text` results from `index` instead of re-parsing the Markdown file
+    /// on every call (`index` is refreshed first, so a changed file is
+    /// transparently re-indexed). The newer list/code/link/table elements
+    /// aren't indexed yet, so those still parse the file directly.
+    pub fn execute_query_with_index(query: Query, index: &mut MarkdownIndex) -> Result<QueryResult, ExecutorError> {
+        let cwd = std::env::current_dir()?;
+        let files = resolve_files(&cwd, &query.file_path)?;
+
+        let mut result = QueryResult::default();
+
+        for file in files {
+            let source_file = display_path(&cwd, &file);
+
+            if let Err(e) = index.refresh(&file) {
+                result.errors.push(FileError { source_file, message: e.to_string() });
+                continue;
+            }
+
+            let needs_direct_parse = query
+                .elements
+                .iter()
+                .any(|element| matches!(element, Element::CodeBlocks | Element::Links | Element::ListItems | Element::Tables));
+            let mut ast = None;
+            if needs_direct_parse {
+                match Self::parse_file(&file) {
+                    Ok(parsed) => ast = Some(parsed),
+                    Err(e) => {
+                        result.errors.push(FileError { source_file, message: e.to_string() });
+                        continue;
+                    }
                 }
-                Element::All => {
-                    headings.extend(Self::extract_headings(&ast));
-                    paragraphs.extend(Self::extract_paragraphs(&ast));
+            }
+
+            for element in &query.elements {
+                match element {
+                    Element::Headings => {
+                        result.headings.extend(Self::tag_headings(&source_file, Self::indexed_headings(index, &file, &query.condition)?));
+                    }
+                    Element::Paragraphs => {
+                        result.paragraphs.extend(Self::tag(&source_file, Self::indexed_text(index, &file, "paragraph", &query.condition)?));
+                    }
+                    Element::Text(text) => {
+                        let pattern = Condition::Cmp { field: Field::Text, op: CmpOp::Contains, value: Value::Str(text.clone()) };
+                        let condition = Some(match &query.condition {
+                            Some(existing) => Condition::And(Box::new(existing.clone()), Box::new(pattern)),
+                            None => pattern,
+                        });
+                        result.matching_text.extend(Self::tag(&source_file, Self::indexed_text(index, &file, "text", &condition)?));
+                    }
+                    Element::All => {
+                        result.headings.extend(Self::tag_headings(&source_file, Self::indexed_headings(index, &file, &query.condition)?));
+                        result.paragraphs.extend(Self::tag(&source_file, Self::indexed_text(index, &file, "paragraph", &query.condition)?));
+                    }
+                    Element::CodeBlocks | Element::Links | Element::ListItems | Element::Tables => {
+                        if let Some(ast) = &ast {
+                            Self::collect_direct(&source_file, element, ast, &query.condition, &mut result);
+                        }
+                    }
                 }
             }
         }
 
-        Ok(QueryResult {
-            headings,
-            paragraphs,
-            matching_text,
-        })
+        Ok(result)
     }
 
-    fn extract_headings(root: &Node) -> Vec<String> {
-        let mut headings = Vec::new();
-        Self::extract_headings_recursive(root, &mut headings);
-        headings
+    fn parse_file(file: &Path) -> Result<Node, ExecutorError> {
+        let markdown_content = std::fs::read_to_string(file)?;
+        to_mdast(&markdown_content, &ParseOptions::gfm()).map_err(|e| ExecutorError::MarkdownParseError(e.to_string()))
     }
 
-    fn extract_headings_recursive(node: &Node, headings: &mut Vec<String>) {
-        match node {
-            Node::Heading(heading) => {
-                headings.push(Self::heading_to_string(heading));
+    /// Extracts a single `element` directly from an already-parsed AST and
+    /// appends it to `result`, tagged with `source_file`.
+    fn collect_direct(source_file: &str, element: &Element, ast: &Node, condition: &Option<Condition>, result: &mut QueryResult) {
+        match element {
+            Element::Headings => {
+                result.headings.extend(Self::tag_headings(source_file, Self::matching_headings(ast, condition)));
             }
-            Node::Root(root) => {
-                for child in &root.children {
-                    Self::extract_headings_recursive(child, headings);
+            Element::Paragraphs => {
+                result.paragraphs.extend(Self::tag(source_file, Self::matching_paragraphs(ast, condition)));
+            }
+            Element::Text(text) => {
+                result.matching_text.extend(Self::tag(source_file, Self::extract_matching_text(ast, text, condition)));
+            }
+            Element::All => {
+                result.headings.extend(Self::tag_headings(source_file, Self::matching_headings(ast, condition)));
+                result.paragraphs.extend(Self::tag(source_file, Self::matching_paragraphs(ast, condition)));
+            }
+            Element::CodeBlocks => {
+                for (language, code) in Self::extract_code_blocks(ast) {
+                    if Self::condition_matches(condition, None, &code, "code") {
+                        result.code_blocks.push(CodeMatch { source_file: source_file.to_string(), language, code });
+                    }
+                }
+            }
+            Element::Links => {
+                for (url, text) in Self::extract_links(ast) {
+                    if Self::condition_matches(condition, None, &text, "link") {
+                        result.links.push(LinkMatch { source_file: source_file.to_string(), url, text });
+                    }
+                }
+            }
+            Element::ListItems => {
+                result.list_items.extend(Self::tag(source_file, Self::matching_list_items(ast, condition)));
+            }
+            Element::Tables => {
+                for rows in Self::extract_tables(ast) {
+                    let flattened = rows.iter().flatten().cloned().collect::<Vec<_>>().join(" ");
+                    if Self::condition_matches(condition, None, &flattened, "table") {
+                        result.tables.push(TableMatch { source_file: source_file.to_string(), rows });
+                    }
                 }
             }
-            _ => {}
         }
     }
 
+    fn tag(source_file: &str, values: Vec<String>) -> Vec<Match> {
+        values
+            .into_iter()
+            .map(|value| Match { source_file: source_file.to_string(), level: None, value })
+            .collect()
+    }
+
+    fn tag_headings(source_file: &str, values: Vec<(Option<i64>, String)>) -> Vec<Match> {
+        values
+            .into_iter()
+            .map(|(level, value)| Match { source_file: source_file.to_string(), level, value })
+            .collect()
+    }
+
+    fn indexed_text(
+        index: &MarkdownIndex,
+        file_path: &Path,
+        node_type: &str,
+        condition: &Option<Condition>,
+    ) -> Result<Vec<String>, ExecutorError> {
+        Ok(index
+            .query(file_path, node_type, condition)?
+            .into_iter()
+            .map(|node| node.text)
+            .collect())
+    }
+
+    fn indexed_headings(
+        index: &MarkdownIndex,
+        file_path: &Path,
+        condition: &Option<Condition>,
+    ) -> Result<Vec<(Option<i64>, String)>, ExecutorError> {
+        Ok(index
+            .query(file_path, "heading", condition)?
+            .into_iter()
+            .map(|node| (node.heading_level, node.text))
+            .collect())
+    }
+
+    fn matching_headings(root: &Node, condition: &Option<Condition>) -> Vec<(Option<i64>, String)> {
+        Self::extract_headings(root)
+            .into_iter()
+            .filter(|(level, text)| Self::condition_matches(condition, Some(*level as i64), text, "heading"))
+            .map(|(level, text)| (Some(level as i64), text))
+            .collect()
+    }
+
+    fn matching_paragraphs(root: &Node, condition: &Option<Condition>) -> Vec<String> {
+        Self::extract_paragraphs(root)
+            .into_iter()
+            .filter(|text| Self::condition_matches(condition, None, text, "paragraph"))
+            .collect()
+    }
+
+    fn matching_list_items(root: &Node, condition: &Option<Condition>) -> Vec<String> {
+        Self::extract_list_items(root)
+            .into_iter()
+            .filter(|text| Self::condition_matches(condition, None, text, "listitem"))
+            .collect()
+    }
+
+    fn extract_headings(root: &Node) -> Vec<(u8, String)> {
+        let mut headings = Vec::new();
+        walk_nodes(root, &mut |node| {
+            if let Node::Heading(heading) = node {
+                headings.push((heading.depth, node_text(node)));
+            }
+        });
+        headings
+    }
+
     fn extract_paragraphs(root: &Node) -> Vec<String> {
         let mut paragraphs = Vec::new();
-        Self::extract_paragraphs_recursive(root, &mut paragraphs);
+        walk_nodes(root, &mut |node| {
+            if let Node::Paragraph(_) = node {
+                paragraphs.push(node_text(node));
+            }
+        });
         paragraphs
     }
 
-    fn extract_paragraphs_recursive(node: &Node, paragraphs: &mut Vec<String>) {
-        match node {
-            Node::Paragraph(paragraph) => {
-                paragraphs.push(Self::paragraph_to_string(paragraph));
-            }
-            Node::Root(root) => {
-                for child in &root.children {
-                    Self::extract_paragraphs_recursive(child, paragraphs);
+    fn extract_matching_text(root: &Node, text: &str, condition: &Option<Condition>) -> Vec<String> {
+        let mut matching_text = Vec::new();
+        walk_nodes(root, &mut |node| {
+            if let Node::Text(text_node) = node {
+                if text_node.value.contains(text) && Self::condition_matches(condition, None, &text_node.value, "text") {
+                    matching_text.push(text_node.value.clone());
                 }
             }
-            _ => {}
-        }
+        });
+        matching_text
     }
 
-    fn extract_matching_text(root: &Node, text: &str) -> Vec<String> {
-        let mut matching_text = Vec::new();
-        Self::extract_matching_text_recursive(root, text, &mut matching_text);
-        matching_text
+    fn extract_code_blocks(root: &Node) -> Vec<(Option<String>, String)> {
+        let mut code_blocks = Vec::new();
+        walk_nodes(root, &mut |node| {
+            if let Node::Code(code) = node {
+                code_blocks.push((code.lang.clone(), code.value.clone()));
+            }
+        });
+        code_blocks
     }
 
-    fn extract_matching_text_recursive(node: &Node, text: &str, matching_text: &mut Vec<String>) {
-        match node {
-            Node::Text(text_node) => {
-                if text_node.value.contains(text) {
-                    matching_text.push(text_node.value.clone());
-                }
+    fn extract_links(root: &Node) -> Vec<(String, String)> {
+        let mut links = Vec::new();
+        walk_nodes(root, &mut |node| {
+            if let Node::Link(link) = node {
+                links.push((link.url.clone(), node_text(node)));
             }
-            Node::Root(root) => {
-                for child in &root.children {
-                    Self::extract_matching_text_recursive(child, text, matching_text);
-                }
+        });
+        links
+    }
+
+    fn extract_list_items(root: &Node) -> Vec<String> {
+        let mut list_items = Vec::new();
+        walk_nodes(root, &mut |node| {
+            if let Node::ListItem(_) = node {
+                list_items.push(own_list_item_text(node));
+            }
+        });
+        list_items
+    }
+
+    fn extract_tables(root: &Node) -> Vec<Vec<Vec<String>>> {
+        let mut tables = Vec::new();
+        walk_nodes(root, &mut |node| {
+            if let Node::Table(table) = node {
+                let rows = table
+                    .children
+                    .iter()
+                    .filter_map(|row| match row {
+                        Node::TableRow(row) => Some(row.children.iter().map(node_text).collect()),
+                        _ => None,
+                    })
+                    .collect();
+                tables.push(rows);
             }
-            _ => {}
+        });
+        tables
+    }
+
+    fn condition_matches(condition: &Option<Condition>, level: Option<i64>, text: &str, node_type: &str) -> bool {
+        match condition {
+            None => true,
+            Some(condition) => Self::eval_condition(condition, level, text, node_type),
         }
     }
-    
-    fn heading_to_string(heading: &Heading) -> String {
-        let mut result = String::new();
-        for child in &heading.children {
-            if let Node::Text(text_node) = child {
-                result.push_str(&text_node.value);
+
+    fn eval_condition(condition: &Condition, level: Option<i64>, text: &str, node_type: &str) -> bool {
+        match condition {
+            Condition::Cmp { field, op, value } => {
+                let lhs = match field {
+                    Field::Level => match level {
+                        Some(level) => Value::Int(level),
+                        None => return false,
+                    },
+                    Field::Text => Value::Str(text.to_string()),
+                    Field::Type => Value::Str(node_type.to_string()),
+                };
+                Self::compare_values(&lhs, *op, value)
             }
+            Condition::And(lhs, rhs) => {
+                Self::eval_condition(lhs, level, text, node_type) && Self::eval_condition(rhs, level, text, node_type)
+            }
+            Condition::Or(lhs, rhs) => {
+                Self::eval_condition(lhs, level, text, node_type) || Self::eval_condition(rhs, level, text, node_type)
+            }
+            Condition::Not(inner) => !Self::eval_condition(inner, level, text, node_type),
+        }
+    }
+
+    fn compare_values(lhs: &Value, op: CmpOp, rhs: &Value) -> bool {
+        match (lhs, rhs) {
+            (Value::Int(a), Value::Int(b)) => match op {
+                CmpOp::Eq => a == b,
+                CmpOp::Ne => a != b,
+                CmpOp::Lt => a < b,
+                CmpOp::Gt => a > b,
+                CmpOp::Le => a <= b,
+                CmpOp::Ge => a >= b,
+                CmpOp::Contains => false,
+            },
+            (Value::Str(a), Value::Str(b)) => match op {
+                CmpOp::Eq => a == b,
+                CmpOp::Ne => a != b,
+                CmpOp::Lt => a < b,
+                CmpOp::Gt => a > b,
+                CmpOp::Le => a <= b,
+                CmpOp::Ge => a >= b,
+                CmpOp::Contains => a.contains(b.as_str()),
+            },
+            _ => false,
         }
-        result
     }
-    
-    fn paragraph_to_string(paragraph: &Paragraph) -> String {
-        let mut result = String::new();
-        for child in &paragraph.children {
-            if let Node::Text(text_node) = child {
-                result.push_str(&text_node.value);
+}
+
+fn display_path(cwd: &Path, path: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).to_string_lossy().to_string()
+}
+
+/// Visits every node in `node`'s subtree (including `node` itself), descending
+/// into any node that carries children so elements nested inside blockquotes,
+/// list items, or table cells aren't missed. Also used by [`crate::index`] so
+/// the SQLite-backed path recurses the same way as direct parsing does.
+pub(crate) fn walk_nodes<'a>(node: &'a Node, visit: &mut dyn FnMut(&'a Node)) {
+    visit(node);
+    if let Some(children) = node.children() {
+        for child in children {
+            walk_nodes(child, visit);
+        }
+    }
+}
+
+/// Flattens all `Text` nodes in `node`'s subtree into a single string, so
+/// e.g. a heading containing `**bold**` still yields its plain text.
+pub(crate) fn node_text(node: &Node) -> String {
+    let mut text = String::new();
+    walk_nodes(node, &mut |n| {
+        if let Node::Text(text_node) = n {
+            text.push_str(&text_node.value);
+        }
+    });
+    text
+}
+
+/// Like [`node_text`], but stops descending at a nested `List`/`ListItem`
+/// instead of flattening its text in too -- a list item's own match should
+/// be just its own text, since each nested item already appears as its own
+/// separate match from the outer walk in `extract_list_items`.
+fn own_list_item_text(item: &Node) -> String {
+    let mut text = String::new();
+    fn walk(node: &Node, text: &mut String, is_root: bool) {
+        if !is_root && matches!(node, Node::List(_) | Node::ListItem(_)) {
+            return;
+        }
+        if let Node::Text(text_node) = node {
+            text.push_str(&text_node.value);
+        }
+        if let Some(children) = node.children() {
+            for child in children {
+                walk(child, text, false);
             }
         }
-        result
+    }
+    walk(item, &mut text, true);
+    text
+}
+
+#[cfg(test)]
+mod executor_tests {
+    use super::*;
+    use markdownql::parser::OutputFormat;
+
+    fn write_temp_markdown(name: &str, content: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn plain_query(file_path: &str, element: Element) -> Query {
+        Query { elements: vec![element], file_path: file_path.to_string(), condition: None, format: OutputFormat::Debug }
+    }
+
+    /// The index path must find nodes nested inside blockquotes, list items,
+    /// and tables the same way direct parsing does -- it's the only path
+    /// main.rs's REPL actually queries.
+    #[test]
+    fn test_execute_query_with_index_matches_direct_parse_for_nested_nodes() {
+        let path = write_temp_markdown(
+            "markdownql_executor_test_nested.md",
+            "> ## Nested heading\n\n- A paragraph nested in a list item.\n\n| a | b |\n| - | - |\n| 1 | 2 |\n",
+        );
+        let file_path = path.to_string_lossy().to_string();
+        let mut index = MarkdownIndex::open(":memory:").unwrap();
+
+        let direct = MarkdownQueryExecutor::execute_query(plain_query(&file_path, Element::Headings)).unwrap();
+        let indexed = MarkdownQueryExecutor::execute_query_with_index(plain_query(&file_path, Element::Headings), &mut index).unwrap();
+        assert_eq!(direct.headings, indexed.headings);
+        assert_eq!(indexed.headings.len(), 1);
+        assert_eq!(indexed.headings[0].level, Some(2));
+        assert_eq!(indexed.headings[0].value, "Nested heading");
+
+        let direct = MarkdownQueryExecutor::execute_query(plain_query(&file_path, Element::Paragraphs)).unwrap();
+        let indexed = MarkdownQueryExecutor::execute_query_with_index(plain_query(&file_path, Element::Paragraphs), &mut index).unwrap();
+        assert_eq!(direct.paragraphs, indexed.paragraphs);
+        assert_eq!(indexed.paragraphs.len(), 1);
+        assert_eq!(indexed.paragraphs[0].value, "A paragraph nested in a list item.");
+
+        let indexed = MarkdownQueryExecutor::execute_query_with_index(plain_query(&file_path, Element::Tables), &mut index).unwrap();
+        assert_eq!(indexed.tables.len(), 1);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// A paragraph whose only child is a link still has its link text
+    /// indexed, rather than being served back as an empty string.
+    #[test]
+    fn test_execute_query_with_index_paragraph_containing_only_a_link() {
+        let path = write_temp_markdown(
+            "markdownql_executor_test_link_paragraph.md",
+            "[a link](https://example.com)\n",
+        );
+        let file_path = path.to_string_lossy().to_string();
+        let mut index = MarkdownIndex::open(":memory:").unwrap();
+
+        let indexed = MarkdownQueryExecutor::execute_query_with_index(plain_query(&file_path, Element::Paragraphs), &mut index).unwrap();
+        assert_eq!(indexed.paragraphs.len(), 1);
+        assert_eq!(indexed.paragraphs[0].value, "a link");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_execute_query_code_blocks() {
+        let path = write_temp_markdown(
+            "markdownql_executor_test_code_blocks.md",
+            "```rust\nfn main() {}\n```\n\n```\nplain\n```\n",
+        );
+        let file_path = path.to_string_lossy().to_string();
+
+        let result = MarkdownQueryExecutor::execute_query(plain_query(&file_path, Element::CodeBlocks)).unwrap();
+        assert_eq!(result.code_blocks.len(), 2);
+        assert_eq!(result.code_blocks[0].language, Some("rust".to_string()));
+        assert_eq!(result.code_blocks[0].code, "fn main() {}");
+        assert_eq!(result.code_blocks[1].language, None);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_execute_query_links() {
+        let path = write_temp_markdown(
+            "markdownql_executor_test_links.md",
+            "See [the docs](https://example.com/docs) and [home](https://example.com).\n",
+        );
+        let file_path = path.to_string_lossy().to_string();
+
+        let result = MarkdownQueryExecutor::execute_query(plain_query(&file_path, Element::Links)).unwrap();
+        assert_eq!(result.links.len(), 2);
+        assert_eq!(result.links[0].url, "https://example.com/docs");
+        assert_eq!(result.links[0].text, "the docs");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// A nested list must yield exactly one match per item, each holding
+    /// only its own text -- not its descendants' text folded in too.
+    #[test]
+    fn test_execute_query_list_items_nested() {
+        let path = write_temp_markdown(
+            "markdownql_executor_test_list_items.md",
+            "- Item 1\n  - Nested Item A\n  - Nested Item B\n- Item 2\n",
+        );
+        let file_path = path.to_string_lossy().to_string();
+
+        let result = MarkdownQueryExecutor::execute_query(plain_query(&file_path, Element::ListItems)).unwrap();
+        let values: Vec<&str> = result.list_items.iter().map(|m| m.value.as_str()).collect();
+        assert_eq!(values, vec!["Item 1", "Nested Item A", "Nested Item B", "Item 2"]);
+
+        std::fs::remove_file(&path).unwrap();
     }
 }
@@ -0,0 +1,127 @@
+//! Resolves a `FROM` clause's file path into the concrete set of Markdown
+//! files it refers to, so a query can run across more than one file: a
+//! literal path (`"post.md"`), a directory (`"notes/"`, walked recursively
+//! for every `.md` file), or a simple glob (`"notes/*.md"`).
+
+use std::path::{Path, PathBuf};
+
+/// Expands `pattern` (resolved against `cwd`) into the Markdown files it
+/// refers to. Non-`.md` files are silently skipped; a literal path with no
+/// wildcard is returned as-is even if it isn't a directory, so read/parse
+/// errors surface per-file later instead of here.
+pub fn resolve_files(cwd: &Path, pattern: &str) -> std::io::Result<Vec<PathBuf>> {
+    let joined = cwd.join(pattern);
+
+    if pattern.ends_with('/') || joined.is_dir() {
+        let mut files = Vec::new();
+        walk_markdown_files(&joined, &mut files)?;
+        files.sort();
+        return Ok(files);
+    }
+
+    if pattern.contains('*') {
+        let dir = joined.parent().unwrap_or(&joined).to_path_buf();
+        let glob = joined
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let mut files = Vec::new();
+        if dir.is_dir() {
+            for entry in std::fs::read_dir(&dir)? {
+                let path = entry?.path();
+                let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                if path.is_file() && is_markdown_file(&path) && glob_match(&glob, &file_name) {
+                    files.push(path);
+                }
+            }
+        }
+        files.sort();
+        return Ok(files);
+    }
+
+    Ok(vec![joined])
+}
+
+fn walk_markdown_files(dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            walk_markdown_files(&path, out)?;
+        } else if is_markdown_file(&path) {
+            out.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn is_markdown_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.eq_ignore_ascii_case("md"))
+        .unwrap_or(false)
+}
+
+/// A small `*`-only glob matcher: each `*` matches any run of characters,
+/// and the non-`*` segments must appear in order.
+fn glob_match(pattern: &str, candidate: &str) -> bool {
+    if !pattern.contains('*') {
+        return pattern == candidate;
+    }
+
+    let segments: Vec<&str> = pattern.split('*').collect();
+    let last = segments.len() - 1;
+    let mut cursor = 0;
+
+    for (i, segment) in segments.iter().enumerate() {
+        if segment.is_empty() {
+            continue;
+        }
+
+        if i == 0 {
+            if !candidate[cursor..].starts_with(segment) {
+                return false;
+            }
+            cursor += segment.len();
+        } else if i == last {
+            if !candidate[cursor..].ends_with(segment) {
+                return false;
+            }
+        } else {
+            match candidate[cursor..].find(segment) {
+                Some(offset) => cursor += offset + segment.len(),
+                None => return false,
+            }
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod files_tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match_star_suffix() {
+        assert!(glob_match("*.md", "post.md"));
+        assert!(!glob_match("*.md", "post.txt"));
+    }
+
+    #[test]
+    fn test_glob_match_exact() {
+        assert!(glob_match("post.md", "post.md"));
+        assert!(!glob_match("post.md", "other.md"));
+    }
+
+    #[test]
+    fn test_glob_match_middle_wildcard() {
+        assert!(glob_match("post-*-draft.md", "post-123-draft.md"));
+        assert!(!glob_match("post-*-draft.md", "post-123-final.md"));
+    }
+}
@@ -0,0 +1,250 @@
+//! A persistent SQLite cache of extracted Markdown nodes, so that querying
+//! the same files repeatedly doesn't mean re-reading and re-parsing them
+//! from disk every time.
+//!
+//! Each indexed file contributes one row per extracted heading, paragraph,
+//! or text node to a single `nodes` table. Before serving a query, the
+//! index compares the file's on-disk `mtime` against the one it last
+//! indexed; only a changed (or never-seen) file is re-parsed.
+
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use markdown::mdast::Node;
+use markdown::to_mdast;
+use markdown::ParseOptions;
+use rusqlite::{params, Connection, ToSql};
+use thiserror::Error;
+
+use crate::executor::{node_text, walk_nodes};
+use crate::parser::{CmpOp, Condition, Field, Value};
+
+#[derive(Error, Debug)]
+pub enum IndexError {
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("Error reading file: {0}")]
+    FileReadError(#[from] std::io::Error),
+
+    #[error("Error parsing Markdown: {0}")]
+    MarkdownParseError(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct IndexedNode {
+    pub heading_level: Option<i64>,
+    pub text: String,
+}
+
+pub struct MarkdownIndex {
+    conn: Connection,
+}
+
+impl MarkdownIndex {
+    pub fn open(db_path: impl AsRef<Path>) -> Result<Self, IndexError> {
+        let conn = Connection::open(db_path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS nodes (
+                file_path TEXT NOT NULL,
+                mtime INTEGER NOT NULL,
+                node_type TEXT NOT NULL,
+                heading_level INTEGER,
+                text TEXT NOT NULL,
+                ordinal INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_nodes_file_path ON nodes(file_path);",
+        )?;
+        Ok(Self { conn })
+    }
+
+    /// Re-parses `file_path` and replaces its indexed rows if the file's
+    /// `mtime` no longer matches what's stored; otherwise does nothing.
+    pub fn refresh(&mut self, file_path: &Path) -> Result<(), IndexError> {
+        let path_key = file_path.to_string_lossy();
+        let mtime = file_mtime(file_path)?;
+
+        let stored_mtime: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT mtime FROM nodes WHERE file_path = ?1 LIMIT 1",
+                params![path_key],
+                |row| row.get(0),
+            )
+            .ok();
+
+        if stored_mtime == Some(mtime) {
+            return Ok(());
+        }
+
+        let markdown_content = std::fs::read_to_string(file_path)?;
+        let ast = to_mdast(&markdown_content, &ParseOptions::gfm())
+            .map_err(|e| IndexError::MarkdownParseError(e.to_string()))?;
+
+        let mut nodes = Vec::new();
+        collect_nodes(&ast, &mut nodes);
+
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM nodes WHERE file_path = ?1", params![path_key])?;
+        for (ordinal, (node_type, heading_level, text)) in nodes.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO nodes (file_path, mtime, node_type, heading_level, text, ordinal)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![path_key, mtime, node_type, heading_level, text, ordinal as i64],
+            )?;
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Returns indexed `node_type` rows for `file_path`, pushing `condition`
+    /// down into the SQL `WHERE` clause when present.
+    pub fn query(
+        &self,
+        file_path: &Path,
+        node_type: &str,
+        condition: &Option<Condition>,
+    ) -> Result<Vec<IndexedNode>, IndexError> {
+        let mut sql = String::from(
+            "SELECT heading_level, text FROM nodes WHERE file_path = ? AND node_type = ?",
+        );
+        let mut sql_params: Vec<Box<dyn ToSql>> = vec![
+            Box::new(file_path.to_string_lossy().to_string()),
+            Box::new(node_type.to_string()),
+        ];
+
+        if let Some(condition) = condition {
+            let (clause, mut condition_params) = condition_to_sql(condition);
+            sql.push_str(" AND (");
+            sql.push_str(&clause);
+            sql.push(')');
+            sql_params.append(&mut condition_params);
+        }
+
+        sql.push_str(" ORDER BY ordinal");
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let param_refs: Vec<&dyn ToSql> = sql_params.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt.query_map(param_refs.as_slice(), |row| {
+            Ok(IndexedNode {
+                heading_level: row.get(0)?,
+                text: row.get(1)?,
+            })
+        })?;
+
+        rows.collect::<Result<Vec<_>, _>>().map_err(IndexError::from)
+    }
+}
+
+fn file_mtime(file_path: &Path) -> Result<i64, std::io::Error> {
+    let modified = std::fs::metadata(file_path)?.modified()?;
+    let since_epoch = modified.duration_since(UNIX_EPOCH).unwrap_or_default();
+    Ok(since_epoch.as_secs() as i64)
+}
+
+/// Walks `root`'s entire subtree the same way [`crate::executor`] does for
+/// direct parsing, so headings/paragraphs/text nested inside blockquotes,
+/// list items, or table cells are indexed too, not just direct children of
+/// the document root.
+fn collect_nodes(root: &Node, out: &mut Vec<(&'static str, Option<i64>, String)>) {
+    walk_nodes(root, &mut |node| match node {
+        Node::Heading(heading) => {
+            out.push(("heading", Some(heading.depth as i64), node_text(node)));
+        }
+        Node::Paragraph(_) => {
+            out.push(("paragraph", None, node_text(node)));
+        }
+        Node::Text(text_node) => {
+            out.push(("text", None, text_node.value.clone()));
+        }
+        _ => {}
+    });
+}
+
+/// Translates a `Condition` tree into a SQL boolean expression plus its
+/// positional parameters, in the same left-to-right order as the `?`
+/// placeholders they belong to.
+fn condition_to_sql(condition: &Condition) -> (String, Vec<Box<dyn ToSql>>) {
+    match condition {
+        Condition::Cmp { field, op, value } => {
+            let column = match field {
+                Field::Level => "heading_level",
+                Field::Text => "text",
+                Field::Type => "node_type",
+            };
+            let param: Box<dyn ToSql> = match value {
+                Value::Int(v) => Box::new(*v),
+                Value::Str(s) => Box::new(s.clone()),
+            };
+
+            let clause = match op {
+                CmpOp::Eq => format!("{column} = ?"),
+                CmpOp::Ne => format!("{column} != ?"),
+                CmpOp::Lt => format!("{column} < ?"),
+                CmpOp::Gt => format!("{column} > ?"),
+                CmpOp::Le => format!("{column} <= ?"),
+                CmpOp::Ge => format!("{column} >= ?"),
+                CmpOp::Contains => format!("{column} LIKE '%' || ? || '%'"),
+            };
+
+            (clause, vec![param])
+        }
+        Condition::And(lhs, rhs) => combine_sql(lhs, rhs, "AND"),
+        Condition::Or(lhs, rhs) => combine_sql(lhs, rhs, "OR"),
+        Condition::Not(inner) => {
+            let (clause, params) = condition_to_sql(inner);
+            (format!("NOT ({clause})"), params)
+        }
+    }
+}
+
+fn combine_sql(lhs: &Condition, rhs: &Condition, op: &str) -> (String, Vec<Box<dyn ToSql>>) {
+    let (lhs_clause, mut lhs_params) = condition_to_sql(lhs);
+    let (rhs_clause, rhs_params) = condition_to_sql(rhs);
+    lhs_params.extend(rhs_params);
+    (format!("({lhs_clause} {op} {rhs_clause})"), lhs_params)
+}
+
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+
+    fn write_temp_markdown(name: &str, content: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_refresh_and_query_roundtrip() {
+        let path = write_temp_markdown(
+            "markdownql_index_test.md",
+            "# Title\n\nSome paragraph about rust.\n",
+        );
+        let mut index = MarkdownIndex::open(":memory:").unwrap();
+
+        index.refresh(&path).unwrap();
+
+        let headings = index.query(&path, "heading", &None).unwrap();
+        assert_eq!(headings.len(), 1);
+        assert_eq!(headings[0].heading_level, Some(1));
+        assert_eq!(headings[0].text, "Title");
+
+        let condition = Some(Condition::Cmp {
+            field: Field::Text,
+            op: CmpOp::Contains,
+            value: Value::Str(String::from("rust")),
+        });
+        let paragraphs = index.query(&path, "paragraph", &condition).unwrap();
+        assert_eq!(paragraphs.len(), 1);
+
+        // Refreshing an unchanged file must be a no-op, not a duplicate insert.
+        index.refresh(&path).unwrap();
+        let headings_again = index.query(&path, "heading", &None).unwrap();
+        assert_eq!(headings_again.len(), 1);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,9 @@
+extern crate self as markdownql;
+
+pub mod tokenizer;
+pub mod parser;
+pub mod executor;
+pub mod diagnostics;
+pub mod index;
+pub mod files;
+pub mod output;
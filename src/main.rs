@@ -1,6 +1,9 @@
 use markdownql::{
+    diagnostics::{render_parse_error, render_tokenization_error},
     executor::MarkdownQueryExecutor,
-    parser::parse_query,
+    index::MarkdownIndex,
+    output::{to_csv, to_json},
+    parser::{parse_query, OutputFormat},
     tokenizer::tokenize,
 };
 use rustyline::error::ReadlineError;
@@ -12,6 +15,15 @@ fn main() -> Result<()> {
     if rl.load_history("history.txt").is_err() {
         println!("No previous history.");
     }
+
+    let mut index = match MarkdownIndex::open(".markdownql.db") {
+        Ok(index) => Some(index),
+        Err(e) => {
+            eprintln!("Error opening index, falling back to direct parsing: {}", e);
+            None
+        }
+    };
+
     loop {
         let readline = rl.readline("markdownql>> ");
         match readline {
@@ -20,20 +32,33 @@ fn main() -> Result<()> {
                     break; // Exit loop if the command is "exit" or "quit"
                 }
                 let _ = rl.add_history_entry(line.as_str());
-                match tokenize(&line) {
-                    Ok(tokens) => {
-                        match parse_query(&tokens) {
-                            Ok(query) => {
-                                let result = MarkdownQueryExecutor::execute_query(query);
-                                match result {
-                                    Ok(result) => println!("{:#?}", result),
-                                    Err(e) => eprintln!("Query execution error: {}", e),
-                                }
-                            }
-                            Err(e) => eprintln!("Error parsing query: {}", e),
+                let (tokens, tokenization_errors) = tokenize(&line);
+                if !tokenization_errors.is_empty() {
+                    for e in &tokenization_errors {
+                        eprintln!("{}", render_tokenization_error(&line, e));
+                    }
+                    continue;
+                }
+                match parse_query(&tokens) {
+                    Ok(query) => {
+                        let format = query.format;
+                        let result = match &mut index {
+                            Some(index) => MarkdownQueryExecutor::execute_query_with_index(query, index),
+                            None => MarkdownQueryExecutor::execute_query(query),
+                        };
+                        match result {
+                            Ok(result) => match format {
+                                OutputFormat::Debug => println!("{:#?}", result),
+                                OutputFormat::Json => match to_json(&result) {
+                                    Ok(json) => println!("{}", json),
+                                    Err(e) => eprintln!("Error formatting result: {}", e),
+                                },
+                                OutputFormat::Csv => print!("{}", to_csv(&result)),
+                            },
+                            Err(e) => eprintln!("Query execution error: {}", e),
                         }
                     }
-                    Err(e) => eprintln!("Tokenization error: {}", e),
+                    Err(e) => eprintln!("{}", render_parse_error(&line, &e)),
                 }
             },
             Err(ReadlineError::Interrupted) => {
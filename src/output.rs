@@ -0,0 +1,112 @@
+//! Renders a [`QueryResult`] into a machine-consumable form, for a `FORMAT
+//! json` / `FORMAT csv` clause. The REPL's default `FORMAT debug` keeps using
+//! `{:#?}` and never touches this module.
+
+use thiserror::Error;
+
+use crate::executor::QueryResult;
+
+#[derive(Error, Debug)]
+pub enum OutputError {
+    #[error("Error serializing result to JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Serializes `result` to pretty-printed JSON.
+pub fn to_json(result: &QueryResult) -> Result<String, OutputError> {
+    Ok(serde_json::to_string_pretty(result)?)
+}
+
+/// Flattens `result` into CSV rows with columns `source_file, element_type,
+/// level, value`. A table's rows are flattened into a single `value` cell
+/// (cells joined with `|`, rows with `;`), since the schema has no room for
+/// a nested shape.
+pub fn to_csv(result: &QueryResult) -> String {
+    let mut csv = String::from("source_file,element_type,level,value\n");
+
+    for m in &result.headings {
+        push_row(&mut csv, &m.source_file, "heading", m.level, &m.value);
+    }
+    for m in &result.paragraphs {
+        push_row(&mut csv, &m.source_file, "paragraph", None, &m.value);
+    }
+    for m in &result.matching_text {
+        push_row(&mut csv, &m.source_file, "text", None, &m.value);
+    }
+    for m in &result.code_blocks {
+        push_row(&mut csv, &m.source_file, "code", None, &m.code);
+    }
+    for m in &result.links {
+        push_row(&mut csv, &m.source_file, "link", None, &format!("{} ({})", m.text, m.url));
+    }
+    for m in &result.list_items {
+        push_row(&mut csv, &m.source_file, "listitem", None, &m.value);
+    }
+    for m in &result.tables {
+        let flattened = m
+            .rows
+            .iter()
+            .map(|row| row.join("|"))
+            .collect::<Vec<_>>()
+            .join(";");
+        push_row(&mut csv, &m.source_file, "table", None, &flattened);
+    }
+    for e in &result.errors {
+        push_row(&mut csv, &e.source_file, "error", None, &e.message);
+    }
+
+    csv
+}
+
+fn push_row(csv: &mut String, source_file: &str, element_type: &str, level: Option<i64>, value: &str) {
+    csv.push_str(&csv_field(source_file));
+    csv.push(',');
+    csv.push_str(element_type);
+    csv.push(',');
+    if let Some(level) = level {
+        csv.push_str(&level.to_string());
+    }
+    csv.push(',');
+    csv.push_str(&csv_field(value));
+    csv.push('\n');
+}
+
+/// Quotes `field` if it contains a comma, quote, or newline, doubling any
+/// embedded quotes, per RFC 4180.
+fn csv_field(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+
+#[cfg(test)]
+mod output_tests {
+    use super::*;
+    use crate::executor::Match;
+
+    #[test]
+    fn test_to_csv_writes_header_and_rows() {
+        let mut result = QueryResult::default();
+        result.headings.push(Match { source_file: String::from("post.md"), level: Some(1), value: String::from("Title") });
+        result.paragraphs.push(Match { source_file: String::from("post.md"), level: None, value: String::from("Hello, world") });
+
+        let csv = to_csv(&result);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "source_file,element_type,level,value");
+        assert_eq!(lines[1], "post.md,heading,1,Title");
+        assert_eq!(lines[2], "post.md,paragraph,,\"Hello, world\"");
+    }
+
+    #[test]
+    fn test_to_json_roundtrips_headings() {
+        let mut result = QueryResult::default();
+        result.headings.push(Match { source_file: String::from("post.md"), level: Some(2), value: String::from("Intro") });
+
+        let json = to_json(&result).unwrap();
+        assert!(json.contains("\"level\": 2"));
+        assert!(json.contains("\"Intro\""));
+    }
+}
@@ -1,4 +1,4 @@
-use markdownql::tokenizer::{Token, Keyword};
+use markdownql::tokenizer::{token_length, token_position, Keyword, Token};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,8 +6,25 @@ pub enum ParseError {
     #[error("Unexpected token: {0}")]
     UnexpectedToken(Token),
 
-    #[error("Unexpected end of input")]
-    UnexpectedEndOfInput,
+    #[error("Unexpected end of input at line {line}, column {column}")]
+    UnexpectedEndOfInput { line: usize, column: usize },
+
+    #[error("Unknown field in condition: {name}")]
+    UnknownField { name: String, line: usize, column: usize },
+}
+
+impl ParseError {
+    /// The `(line, column, length)` this error should be underlined at.
+    pub fn span(&self) -> (usize, usize, usize) {
+        match self {
+            ParseError::UnexpectedToken(token) => {
+                let (line, column) = token_position(token);
+                (line, column, token_length(token))
+            }
+            ParseError::UnexpectedEndOfInput { line, column } => (*line, *column, 1),
+            ParseError::UnknownField { name, line, column } => (*line, *column, name.len()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -15,35 +32,89 @@ pub enum Element {
     Headings,
     Paragraphs,
     Text(String),
+    CodeBlocks,
+    Links,
+    ListItems,
+    Tables,
     All,
 }
 
+/// A field of an extracted element that a `WHERE` clause can compare against.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Field {
+    Level,
+    Text,
+    Type,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    Contains,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Str(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Cmp { field: Field, op: CmpOp, value: Value },
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+}
+
+/// How a [`Query`]'s results should be rendered, set via a trailing
+/// `FORMAT json` / `FORMAT csv` clause. Defaults to `Debug` (the REPL's
+/// original pretty-printed debug dump) when no `FORMAT` clause is given.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Debug,
+    Json,
+    Csv,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Query {
     pub elements: Vec<Element>,
     pub file_path: String,
-    pub condition: Option<String>,
+    pub condition: Option<Condition>,
+    pub format: OutputFormat,
 }
 
 
 pub fn parse_query(tokens: &[Token]) -> Result<Query, ParseError> {
     let mut elements = Vec::new();
     let mut file_path = String::new();
-    let mut condition: Option<String> = None;
+    let mut condition: Option<Condition> = None;
+    let mut format = OutputFormat::default();
 
+    let eof = eof_position(tokens);
     let mut tokens_iter = tokens.iter().peekable();
 
     while let Some(token) = tokens_iter.next() {
         match token {
             Token::Keyword(keyword, _, _) => match keyword {
                 Keyword::SELECT => {
-                    elements.extend(parse_select(&mut tokens_iter)?);
+                    elements.extend(parse_select(&mut tokens_iter, eof)?);
                 }
                 Keyword::FROM => {
-                    file_path = parse_file_path(&mut tokens_iter)?;
+                    file_path = parse_file_path(&mut tokens_iter, eof)?;
                 }
                 Keyword::WHERE => {
-                    condition = parse_condition(&mut tokens_iter)?;
+                    condition = Some(parse_or(&mut tokens_iter, eof)?);
+                }
+                Keyword::FORMAT => {
+                    format = parse_format(&mut tokens_iter, eof)?;
                 }
                 _ => {}
             },
@@ -55,10 +126,27 @@ pub fn parse_query(tokens: &[Token]) -> Result<Query, ParseError> {
         elements,
         file_path,
         condition,
+        format,
     })
 }
 
-fn parse_select(tokens_iter: &mut std::iter::Peekable<std::slice::Iter<Token>>) -> Result<Vec<Element>, ParseError> {
+/// The `(line, column)` right after the last token, used to anchor "unexpected
+/// end of input" errors at a real position instead of losing the span.
+fn eof_position(tokens: &[Token]) -> (usize, usize) {
+    match tokens.last() {
+        Some(token) => {
+            let (line, column) = token_position(token);
+            (line, column + token_length(token))
+        }
+        None => (1, 1),
+    }
+}
+
+fn eof_error(eof: (usize, usize)) -> ParseError {
+    ParseError::UnexpectedEndOfInput { line: eof.0, column: eof.1 }
+}
+
+fn parse_select(tokens_iter: &mut std::iter::Peekable<std::slice::Iter<Token>>, eof: (usize, usize)) -> Result<Vec<Element>, ParseError> {
     let mut elements = Vec::new();
 
     while let Some(token) = tokens_iter.next() {
@@ -70,11 +158,15 @@ fn parse_select(tokens_iter: &mut std::iter::Peekable<std::slice::Iter<Token>>)
                 elements.push(match identifier.as_str() {
                     "headings" => Element::Headings,
                     "paragraphs" => Element::Paragraphs,
+                    "code" => Element::CodeBlocks,
+                    "links" => Element::Links,
+                    "lists" => Element::ListItems,
+                    "tables" => Element::Tables,
                     "text" => {
                         if let Some(Token::StringLiteral(text, _, _)) = tokens_iter.next() {
                             Element::Text(text.clone())
                         } else {
-                            return Err(ParseError::UnexpectedEndOfInput);
+                            return Err(eof_error(eof));
                         }
                     }
                     _ => Element::Text(identifier.clone()),
@@ -97,19 +189,116 @@ fn parse_select(tokens_iter: &mut std::iter::Peekable<std::slice::Iter<Token>>)
     Ok(elements)
 }
 
-fn parse_file_path(tokens_iter: &mut std::iter::Peekable<std::slice::Iter<Token>>) -> Result<String, ParseError> {
+fn parse_file_path(tokens_iter: &mut std::iter::Peekable<std::slice::Iter<Token>>, eof: (usize, usize)) -> Result<String, ParseError> {
     if let Some(token) = tokens_iter.next() {
         match token {
             Token::StringLiteral(file_path, _, _) => Ok(file_path.clone()),
             _ => Err(ParseError::UnexpectedToken(token.clone())),
         }
     } else {
-        Err(ParseError::UnexpectedEndOfInput)
+        Err(eof_error(eof))
+    }
+}
+
+fn parse_format(tokens_iter: &mut std::iter::Peekable<std::slice::Iter<Token>>, eof: (usize, usize)) -> Result<OutputFormat, ParseError> {
+    match tokens_iter.next() {
+        Some(token @ Token::Identifier(name, _, _)) => match name.as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "debug" => Ok(OutputFormat::Debug),
+            _ => Err(ParseError::UnexpectedToken(token.clone())),
+        },
+        Some(token) => Err(ParseError::UnexpectedToken(token.clone())),
+        None => Err(eof_error(eof)),
+    }
+}
+
+// Condition grammar, in ascending precedence (NOT binds tightest, OR loosest):
+//
+//   or_expr  := and_expr (OR and_expr)*
+//   and_expr := not_expr (AND not_expr)*
+//   not_expr := NOT not_expr | cmp
+//   cmp      := field operator value
+
+fn parse_or(tokens_iter: &mut std::iter::Peekable<std::slice::Iter<Token>>, eof: (usize, usize)) -> Result<Condition, ParseError> {
+    let mut condition = parse_and(tokens_iter, eof)?;
+
+    while let Some(Token::Keyword(Keyword::OR, _, _)) = tokens_iter.peek() {
+        tokens_iter.next();
+        let rhs = parse_and(tokens_iter, eof)?;
+        condition = Condition::Or(Box::new(condition), Box::new(rhs));
+    }
+
+    Ok(condition)
+}
+
+fn parse_and(tokens_iter: &mut std::iter::Peekable<std::slice::Iter<Token>>, eof: (usize, usize)) -> Result<Condition, ParseError> {
+    let mut condition = parse_not(tokens_iter, eof)?;
+
+    while let Some(Token::Keyword(Keyword::AND, _, _)) = tokens_iter.peek() {
+        tokens_iter.next();
+        let rhs = parse_not(tokens_iter, eof)?;
+        condition = Condition::And(Box::new(condition), Box::new(rhs));
+    }
+
+    Ok(condition)
+}
+
+fn parse_not(tokens_iter: &mut std::iter::Peekable<std::slice::Iter<Token>>, eof: (usize, usize)) -> Result<Condition, ParseError> {
+    if let Some(Token::Keyword(Keyword::NOT, _, _)) = tokens_iter.peek() {
+        tokens_iter.next();
+        return Ok(Condition::Not(Box::new(parse_not(tokens_iter, eof)?)));
+    }
+
+    parse_cmp(tokens_iter, eof)
+}
+
+fn parse_cmp(tokens_iter: &mut std::iter::Peekable<std::slice::Iter<Token>>, eof: (usize, usize)) -> Result<Condition, ParseError> {
+    let field = match tokens_iter.next() {
+        Some(token @ Token::Identifier(name, _, _)) => parse_field(name, token)?,
+        Some(token) => return Err(ParseError::UnexpectedToken(token.clone())),
+        None => return Err(eof_error(eof)),
+    };
+
+    let op = match tokens_iter.next() {
+        Some(Token::Operator(op, _, _)) => parse_cmp_op(op)?,
+        Some(Token::Keyword(Keyword::CONTAINS, _, _)) => CmpOp::Contains,
+        Some(token) => return Err(ParseError::UnexpectedToken(token.clone())),
+        None => return Err(eof_error(eof)),
+    };
+
+    let value = match tokens_iter.next() {
+        Some(Token::IntegerLiteral(value, _, _)) => Value::Int(*value),
+        Some(Token::StringLiteral(value, _, _)) => Value::Str(value.clone()),
+        Some(token) => return Err(ParseError::UnexpectedToken(token.clone())),
+        None => return Err(eof_error(eof)),
+    };
+
+    Ok(Condition::Cmp { field, op, value })
+}
+
+fn parse_field(name: &str, token: &Token) -> Result<Field, ParseError> {
+    match name {
+        "level" => Ok(Field::Level),
+        "text" => Ok(Field::Text),
+        "type" => Ok(Field::Type),
+        _ => {
+            let (line, column) = token_position(token);
+            Err(ParseError::UnknownField { name: name.to_string(), line, column })
+        }
     }
 }
 
-fn parse_condition(_tokens_iter: &mut std::iter::Peekable<std::slice::Iter<Token>>) -> Result<Option<String>, ParseError> {
-    unimplemented!("not yet supported")
+fn parse_cmp_op(op: &str) -> Result<CmpOp, ParseError> {
+    match op {
+        "=" => Ok(CmpOp::Eq),
+        "!=" => Ok(CmpOp::Ne),
+        "<" => Ok(CmpOp::Lt),
+        ">" => Ok(CmpOp::Gt),
+        "<=" => Ok(CmpOp::Le),
+        ">=" => Ok(CmpOp::Ge),
+        _ => unreachable!("tokenizer only produces known comparison operators"),
+    }
 }
 
 
@@ -130,8 +319,132 @@ mod parser_tests {
             elements: vec![Element::All],
             file_path: String::from("examples/posts/hello-world.md"),
             condition: None,
+            format: OutputFormat::Debug,
+        };
+
+        assert_eq!(parse_query(&tokens).unwrap(), expected_query);
+    }
+
+    #[test]
+    fn test_parse_query_with_where_eq() {
+        let tokens = vec![
+            Token::Keyword(Keyword::SELECT, 1, 1),
+            Token::Identifier(String::from("headings"), 1, 8),
+            Token::Keyword(Keyword::FROM, 1, 17),
+            Token::StringLiteral(String::from("post.md"), 1, 23),
+            Token::Keyword(Keyword::WHERE, 1, 33),
+            Token::Identifier(String::from("level"), 1, 39),
+            Token::Operator(String::from("="), 1, 45),
+            Token::IntegerLiteral(2, 1, 47),
+        ];
+
+        let expected_query = Query {
+            elements: vec![Element::Headings],
+            file_path: String::from("post.md"),
+            condition: Some(Condition::Cmp {
+                field: Field::Level,
+                op: CmpOp::Eq,
+                value: Value::Int(2),
+            }),
+            format: OutputFormat::Debug,
         };
 
         assert_eq!(parse_query(&tokens).unwrap(), expected_query);
     }
+
+    #[test]
+    fn test_parse_query_with_and_or_precedence() {
+        // level = 1 OR level = 2 AND text CONTAINS "rust"
+        // should parse as: level = 1 OR (level = 2 AND text CONTAINS "rust")
+        let tokens = vec![
+            Token::Keyword(Keyword::WHERE, 1, 1),
+            Token::Identifier(String::from("level"), 1, 1),
+            Token::Operator(String::from("="), 1, 1),
+            Token::IntegerLiteral(1, 1, 1),
+            Token::Keyword(Keyword::OR, 1, 1),
+            Token::Identifier(String::from("level"), 1, 1),
+            Token::Operator(String::from("="), 1, 1),
+            Token::IntegerLiteral(2, 1, 1),
+            Token::Keyword(Keyword::AND, 1, 1),
+            Token::Identifier(String::from("text"), 1, 1),
+            Token::Keyword(Keyword::CONTAINS, 1, 1),
+            Token::StringLiteral(String::from("rust"), 1, 1),
+        ];
+
+        let expected = Condition::Or(
+            Box::new(Condition::Cmp { field: Field::Level, op: CmpOp::Eq, value: Value::Int(1) }),
+            Box::new(Condition::And(
+                Box::new(Condition::Cmp { field: Field::Level, op: CmpOp::Eq, value: Value::Int(2) }),
+                Box::new(Condition::Cmp { field: Field::Text, op: CmpOp::Contains, value: Value::Str(String::from("rust")) }),
+            )),
+        );
+
+        let query = parse_query(&tokens).unwrap();
+        assert_eq!(query.condition, Some(expected));
+    }
+
+    #[test]
+    fn test_parse_query_unexpected_end_of_input_has_position() {
+        let tokens = vec![
+            Token::Keyword(Keyword::FROM, 1, 1),
+        ];
+
+        match parse_query(&tokens) {
+            Err(ParseError::UnexpectedEndOfInput { line, column }) => {
+                assert_eq!(line, 1);
+                assert_eq!(column, 5); // right after "FROM"
+            }
+            other => panic!("expected UnexpectedEndOfInput, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_select_new_element_kinds() {
+        let tokens = vec![
+            Token::Keyword(Keyword::SELECT, 1, 1),
+            Token::Identifier(String::from("code"), 1, 1),
+            Token::Punctuation(',', 1, 1),
+            Token::Identifier(String::from("links"), 1, 1),
+            Token::Punctuation(',', 1, 1),
+            Token::Identifier(String::from("lists"), 1, 1),
+            Token::Punctuation(',', 1, 1),
+            Token::Identifier(String::from("tables"), 1, 1),
+            Token::Keyword(Keyword::FROM, 1, 1),
+            Token::StringLiteral(String::from("post.md"), 1, 1),
+        ];
+
+        let query = parse_query(&tokens).unwrap();
+        assert_eq!(
+            query.elements,
+            vec![Element::CodeBlocks, Element::Links, Element::ListItems, Element::Tables]
+        );
+    }
+
+    #[test]
+    fn test_parse_query_with_format_clause() {
+        let tokens = vec![
+            Token::Keyword(Keyword::SELECT, 1, 1),
+            Token::Identifier(String::from("headings"), 1, 1),
+            Token::Keyword(Keyword::FROM, 1, 1),
+            Token::StringLiteral(String::from("post.md"), 1, 1),
+            Token::Keyword(Keyword::FORMAT, 1, 1),
+            Token::Identifier(String::from("json"), 1, 1),
+        ];
+
+        let query = parse_query(&tokens).unwrap();
+        assert_eq!(query.format, OutputFormat::Json);
+    }
+
+    #[test]
+    fn test_parse_query_without_format_clause_defaults_to_debug() {
+        let tokens = vec![
+            Token::Keyword(Keyword::SELECT, 1, 1),
+            Token::Keyword(Keyword::ALL, 1, 1),
+            Token::Keyword(Keyword::FROM, 1, 1),
+            Token::StringLiteral(String::from("post.md"), 1, 1),
+        ];
+
+        let query = parse_query(&tokens).unwrap();
+        assert_eq!(query.format, OutputFormat::Debug);
+    }
 }
@@ -9,12 +9,14 @@ pub enum TokenizationError {
         line: usize,
         column: usize,
     },
+
     #[error("Unexpected escape sequence at line {line}, column {column}: {character}")]
     UnexpectedEscapeSequence {
         character: char,
         line: usize,
         column: usize,
     },
+
     #[error("Unterminated string literal at line {line}, column {column}")]
     UnterminatedStringLiteral {
         line: usize,
@@ -33,6 +35,8 @@ pub enum Token {
     Keyword(Keyword, usize, usize),
     Identifier(String, usize, usize),
     Punctuation(char, usize, usize),
+    Operator(String, usize, usize),
+    IntegerLiteral(i64, usize, usize),
     StringLiteral(String, usize, usize),
 }
 
@@ -42,6 +46,8 @@ impl Token {
             Token::Keyword(keyword, _, _) => format!("Keyword: {:?}", keyword),
             Token::Identifier(identifier, _, _) => format!("Identifier: {}", identifier),
             Token::Punctuation(punct, _, _) => format!("Punctuation: '{}'", punct),
+            Token::Operator(op, _, _) => format!("Operator: '{}'", op),
+            Token::IntegerLiteral(value, _, _) => format!("Integer: {}", value),
             Token::StringLiteral(string, _, _) => format!("String Literal: \"{}\"", string),
         }
     }
@@ -53,12 +59,41 @@ impl fmt::Display for Token {
     }
 }
 
+/// The `(line, column)` this token started at.
+pub fn token_position(token: &Token) -> (usize, usize) {
+    match token {
+        Token::Keyword(_, line, column) => (*line, *column),
+        Token::Identifier(_, line, column) => (*line, *column),
+        Token::Punctuation(_, line, column) => (*line, *column),
+        Token::Operator(_, line, column) => (*line, *column),
+        Token::IntegerLiteral(_, line, column) => (*line, *column),
+        Token::StringLiteral(_, line, column) => (*line, *column),
+    }
+}
+
+/// How many source columns this token spans, for underlining it in a diagnostic.
+pub fn token_length(token: &Token) -> usize {
+    match token {
+        Token::Keyword(keyword, _, _) => keyword.to_string().len(),
+        Token::Identifier(identifier, _, _) => identifier.len(),
+        Token::Punctuation(_, _, _) => 1,
+        Token::Operator(op, _, _) => op.len(),
+        Token::IntegerLiteral(value, _, _) => value.to_string().len(),
+        Token::StringLiteral(string, _, _) => string.len() + 2,
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Keyword {
     SELECT,
     FROM,
     WHERE,
     ALL,
+    AND,
+    OR,
+    NOT,
+    CONTAINS,
+    FORMAT,
 }
 
 impl Keyword {
@@ -68,19 +103,140 @@ impl Keyword {
             Keyword::FROM => String::from("FROM"),
             Keyword::WHERE => String::from("WHERE"),
             Keyword::ALL => String::from("ALL"),
+            Keyword::AND => String::from("AND"),
+            Keyword::OR => String::from("OR"),
+            Keyword::NOT => String::from("NOT"),
+            Keyword::CONTAINS => String::from("CONTAINS"),
+            Keyword::FORMAT => String::from("FORMAT"),
+        }
+    }
+}
+
+/// What kind of token `tokenize` should try to lex starting at the current
+/// character, decided purely by looking at that one character. Each variant
+/// maps to exactly one of the `lex_*` rule functions below, so adding a new
+/// token shape means adding one match arm here and one rule function, rather
+/// than threading new cases through a single monolithic loop body.
+///
+/// Note: this is still a hand-rolled per-character dispatch over a single
+/// mutable scan, not a combinator/grammar crate (no logos/chumsky/nom
+/// dependency was added) -- string-literal and escape handling in
+/// particular still live inline in `tokenize`'s loop rather than as a rule
+/// of their own. It fixes the `*` mis-tokenization and adds per-character
+/// error recovery, but a reader expecting a grammar-driven lexer from this
+/// module's history will still find the same loop shape as the baseline.
+enum Rule {
+    Whitespace,
+    StringLiteral,
+    Word,
+    Star,
+    Punctuation,
+    Operator,
+    Unexpected,
+}
+
+fn classify(c: char) -> Rule {
+    match c {
+        _ if c.is_whitespace() => Rule::Whitespace,
+        '"' => Rule::StringLiteral,
+        '*' => Rule::Star,
+        ',' | '.' => Rule::Punctuation,
+        '=' | '!' | '<' | '>' => Rule::Operator,
+        _ if c.is_alphanumeric() || c == '_' => Rule::Word,
+        _ => Rule::Unexpected,
+    }
+}
+
+/// Flushes the current identifier/keyword/integer buffer into `tokens`, if non-empty.
+fn flush_buffer(buffer: &mut String, tokens: &mut Vec<Token>, line: usize, column: usize) {
+    if buffer.is_empty() {
+        return;
+    }
+
+    let start_column = column - buffer.len();
+
+    if let Ok(value) = buffer.parse::<i64>() {
+        tokens.push(Token::IntegerLiteral(value, line, start_column));
+    } else {
+        match buffer.to_uppercase().as_str() {
+            "SELECT" => tokens.push(Token::Keyword(Keyword::SELECT, line, start_column)),
+            "FROM" => tokens.push(Token::Keyword(Keyword::FROM, line, start_column)),
+            "WHERE" => tokens.push(Token::Keyword(Keyword::WHERE, line, start_column)),
+            "AND" => tokens.push(Token::Keyword(Keyword::AND, line, start_column)),
+            "OR" => tokens.push(Token::Keyword(Keyword::OR, line, start_column)),
+            "NOT" => tokens.push(Token::Keyword(Keyword::NOT, line, start_column)),
+            "CONTAINS" => tokens.push(Token::Keyword(Keyword::CONTAINS, line, start_column)),
+            "FORMAT" => tokens.push(Token::Keyword(Keyword::FORMAT, line, start_column)),
+            _ => tokens.push(Token::Identifier(buffer.clone(), line, start_column)),
         }
     }
+
+    buffer.clear();
 }
 
-pub fn tokenize(input: &str) -> Result<Vec<Token>, TokenizationError> {
+/// Lexes `*` directly as `Keyword::ALL`, rather than relying on it reaching
+/// `flush_buffer` as a one-character "word" — which only worked when `*` was
+/// surrounded by whitespace, and mis-tokenized runs like `SELECT *FROM "x.md"`
+/// as an unknown identifier instead of `ALL` followed by `FROM`.
+fn lex_star(tokens: &mut Vec<Token>, line: usize, column: usize) {
+    tokens.push(Token::Keyword(Keyword::ALL, line, column));
+}
+
+fn lex_punctuation(c: char, tokens: &mut Vec<Token>, line: usize, column: usize) {
+    tokens.push(Token::Punctuation(c, line, column));
+}
+
+/// Lexes `=`, `!=`, `<`, `<=`, `>`, `>=`, consuming a second character of
+/// lookahead when present. A bare `!` isn't a valid operator on its own; it's
+/// recorded as a recoverable error and the character is simply dropped so
+/// the rest of the line still lexes.
+fn lex_operator(
+    c: char,
+    next: Option<char>,
+    tokens: &mut Vec<Token>,
+    errors: &mut Vec<TokenizationError>,
+    line: usize,
+    column: usize,
+) -> bool {
+    let (op, consumes_next) = match (c, next) {
+        ('=', _) => ("=", false),
+        ('!', Some('=')) => ("!=", true),
+        ('<', Some('=')) => ("<=", true),
+        ('>', Some('=')) => (">=", true),
+        ('<', _) => ("<", false),
+        ('>', _) => (">", false),
+        ('!', _) => {
+            errors.push(TokenizationError::UnexpectedCharacter { character: c, line, column });
+            return false;
+        }
+        _ => unreachable!("lex_operator only called for =, !, <, >"),
+    };
+
+    tokens.push(Token::Operator(op.to_string(), line, column));
+    consumes_next
+}
+
+/// Tokenizes `input`, returning every token it could recover alongside every
+/// error it hit along the way: an unexpected character (or a bare `!`, or a
+/// bad string escape) is recorded and skipped rather than aborting the whole
+/// scan, so a line with more than one mistake gets more than one diagnostic.
+/// Only running out of input mid-string is unrecoverable, since there's
+/// nothing left to resume from.
+pub fn tokenize(input: &str) -> (Vec<Token>, Vec<TokenizationError>) {
     let mut tokens = Vec::new();
+    let mut errors = Vec::new();
     let mut buffer = String::new();
     let mut in_string = false;
     let mut escape = false;
     let mut line_number = 1;
     let mut column = 0;
 
-    for c in input.chars() {
+    let chars: Vec<char> = input.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
         match c {
             '\n' => {
                 line_number += 1;
@@ -97,7 +253,7 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, TokenizationError> {
                 't' => buffer.push('\t'),
                 '\\' => buffer.push('\\'),
                 '"' => buffer.push('"'),
-                _ => return Err(TokenizationError::UnexpectedEscapeSequence { character: c, line: line_number, column }),
+                _ => errors.push(TokenizationError::UnexpectedEscapeSequence { character: c, line: line_number, column }),
             }
             escape = false;
         } else if in_string {
@@ -117,52 +273,47 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, TokenizationError> {
                 _ => buffer.push(c),
             }
         } else {
-            if c.is_whitespace() {
-                if !buffer.is_empty() {
-                    match buffer.to_uppercase().as_str() {
-                        "SELECT" => tokens.push(Token::Keyword(Keyword::SELECT, line_number, column - buffer.len())),
-                        "FROM" => tokens.push(Token::Keyword(Keyword::FROM, line_number, column - buffer.len())),
-                        "WHERE" => tokens.push(Token::Keyword(Keyword::WHERE, line_number, column - buffer.len())),
-                        "*" => tokens.push(Token::Keyword(Keyword::ALL, line_number, column - buffer.len())),
-                        _ => tokens.push(Token::Identifier(buffer.clone(), line_number, column - buffer.len())),
-                    }
-                    buffer.clear();
+            match classify(c) {
+                Rule::Whitespace => flush_buffer(&mut buffer, &mut tokens, line_number, column),
+                Rule::StringLiteral => {
+                    flush_buffer(&mut buffer, &mut tokens, line_number, column);
+                    in_string = true;
                 }
-            } else {
-                match c {
-                    '"' => {
-                        in_string = true;
-                    }
-                    ',' | '.' => {
-                        if !buffer.is_empty() {
-                            tokens.push(Token::Identifier(
-                                buffer.clone(),
-                                line_number,
-                                column - buffer.len(),
-                            ));
-                            buffer.clear();
-                        }
-                        tokens.push(Token::Punctuation(c, line_number, column));
+                Rule::Word => buffer.push(c),
+                Rule::Star => {
+                    flush_buffer(&mut buffer, &mut tokens, line_number, column);
+                    lex_star(&mut tokens, line_number, column);
+                }
+                Rule::Punctuation => {
+                    flush_buffer(&mut buffer, &mut tokens, line_number, column);
+                    lex_punctuation(c, &mut tokens, line_number, column);
+                }
+                Rule::Operator => {
+                    flush_buffer(&mut buffer, &mut tokens, line_number, column);
+                    let next = chars.get(i + 1).copied();
+                    let consumed_next = lex_operator(c, next, &mut tokens, &mut errors, line_number, column);
+                    if consumed_next {
+                        i += 1;
+                        column += 1;
                     }
-                    _ => buffer.push(c),
+                }
+                Rule::Unexpected => {
+                    flush_buffer(&mut buffer, &mut tokens, line_number, column);
+                    errors.push(TokenizationError::UnexpectedCharacter { character: c, line: line_number, column });
                 }
             }
         }
-    }
 
-    if escape {
-        return Err(TokenizationError::UnterminatedStringLiteral { line: line_number, column });
+        i += 1;
     }
 
-    if !buffer.is_empty() {
-        if in_string {
-            return Err(TokenizationError::UnterminatedStringLiteral { line: line_number, column });
-        } else {
-            tokens.push(Token::Identifier(buffer.clone(), line_number, column - buffer.len()));
-        }
+    if escape || in_string {
+        errors.push(TokenizationError::UnterminatedStringLiteral { line: line_number, column });
+    } else {
+        flush_buffer(&mut buffer, &mut tokens, line_number, column);
     }
 
-    Ok(tokens)
+    (tokens, errors)
 }
 
 
@@ -180,6 +331,82 @@ mod tokenizer_tests {
             Token::StringLiteral(String::from("examples/posts/hello-world.md"), 1, 16),
         ];
 
-        assert_eq!(tokenize(input).unwrap(), expected_tokens);
+        let (tokens, errors) = tokenize(input);
+        assert!(errors.is_empty());
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn test_tokenization_where_clause() {
+        let input = "SELECT headings FROM \"post.md\" WHERE level = 2";
+        let expected_tokens = vec![
+            Token::Keyword(Keyword::SELECT, 1, 1),
+            Token::Identifier(String::from("headings"), 1, 8),
+            Token::Keyword(Keyword::FROM, 1, 17),
+            Token::StringLiteral(String::from("post.md"), 1, 23),
+            Token::Keyword(Keyword::WHERE, 1, 32),
+            Token::Identifier(String::from("level"), 1, 38),
+            Token::Operator(String::from("="), 1, 44),
+            Token::IntegerLiteral(2, 1, 45),
+        ];
+
+        let (tokens, errors) = tokenize(input);
+        assert!(errors.is_empty());
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn test_tokenization_not_equal() {
+        let input = "WHERE level != 2";
+        let expected_tokens = vec![
+            Token::Keyword(Keyword::WHERE, 1, 1),
+            Token::Identifier(String::from("level"), 1, 7),
+            Token::Operator(String::from("!="), 1, 13),
+            Token::IntegerLiteral(2, 1, 15),
+        ];
+
+        let (tokens, errors) = tokenize(input);
+        assert!(errors.is_empty());
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn test_tokenization_star_without_surrounding_whitespace() {
+        // Previously `*FROM` got swallowed into a single buffer and mis-tokenized
+        // as an identifier instead of `ALL` followed by `FROM`.
+        let input = "SELECT *FROM \"post.md\"";
+        let expected_tokens = vec![
+            Token::Keyword(Keyword::SELECT, 1, 1),
+            Token::Keyword(Keyword::ALL, 1, 8),
+            Token::Keyword(Keyword::FROM, 1, 9),
+            Token::StringLiteral(String::from("post.md"), 1, 15),
+        ];
+
+        let (tokens, errors) = tokenize(input);
+        assert!(errors.is_empty());
+        assert_eq!(tokens, expected_tokens);
+    }
+
+    #[test]
+    fn test_tokenization_recovers_past_unexpected_characters() {
+        // A single bad character shouldn't stop the rest of the line from
+        // lexing, and both mistakes below should be reported.
+        let input = "SELECT @ headings # FROM \"post.md\"";
+
+        let (tokens, errors) = tokenize(input);
+
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[0], TokenizationError::UnexpectedCharacter { character: '@', .. }));
+        assert!(matches!(errors[1], TokenizationError::UnexpectedCharacter { character: '#', .. }));
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Keyword(Keyword::SELECT, 1, 1),
+                Token::Identifier(String::from("headings"), 1, 10),
+                Token::Keyword(Keyword::FROM, 1, 21),
+                Token::StringLiteral(String::from("post.md"), 1, 27),
+            ]
+        );
     }
 }